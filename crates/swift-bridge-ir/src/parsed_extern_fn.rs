@@ -5,7 +5,10 @@ use proc_macro2::{Ident, TokenStream};
 use quote::{quote, quote_spanned, ToTokens};
 use std::ops::Deref;
 use syn::spanned::Spanned;
-use syn::{FnArg, ForeignItemFn, Lifetime, Pat, Path, ReturnType, Token, Type};
+use syn::{
+    Attribute, FnArg, ForeignItemFn, GenericArgument, Lifetime, Meta, NestedMeta, Pat, Path,
+    PathArguments, ReturnType, Token, Type,
+};
 
 mod to_extern_c_fn;
 mod to_extern_c_param_names_and_types;
@@ -26,9 +29,41 @@ pub(crate) struct ParsedExternFn {
     pub associated_type: Option<BridgedType>,
     pub is_initializer: bool,
     pub host_lang: HostLang,
+    /// The `#[swift_bridge(namespace = "...")]` that this function's module
+    /// (or extern block) was declared with, if any. Threaded into the
+    /// mangled symbol name so that two bridge modules that both declare a
+    /// `Foo` type don't produce colliding `extern "C"` symbols when linked
+    /// into the same binary.
+    pub namespace: Option<String>,
 }
 
 impl ParsedExternFn {
+    /// Builds a `ParsedExternFn`, resolving `namespace` from the
+    /// `#[swift_bridge(namespace = "...")]` attribute (if any) on the
+    /// module or extern block `func` was declared in.
+    ///
+    /// This is the one real call site for `parse_namespace_attr`: whichever
+    /// module-level parser builds a `ParsedExternFn` for each function in a
+    /// `#[swift_bridge::bridge] mod ffi { ... }` should construct it through
+    /// here (passing the surrounding module/extern-block's attributes)
+    /// rather than populating the fields by hand, so that namespace
+    /// resolution can't be forgotten at a construction site.
+    pub(crate) fn new(
+        func: ForeignItemFn,
+        associated_type: Option<BridgedType>,
+        is_initializer: bool,
+        host_lang: HostLang,
+        surrounding_attrs: &[Attribute],
+    ) -> Self {
+        ParsedExternFn {
+            func,
+            associated_type,
+            is_initializer,
+            host_lang,
+            namespace: Self::parse_namespace_attr(surrounding_attrs),
+        }
+    }
+
     pub fn is_method(&self) -> bool {
         self.func.sig.receiver().is_some()
     }
@@ -74,7 +109,23 @@ impl ParsedExternFn {
                 quote! {}
             }
             ReturnType::Type(arrow, ty) => {
-                if let Some(built_in) = BuiltInType::with_type(&ty) {
+                if let Some((ok_ty, _err_ty)) = self.fallible_return_types() {
+                    let ok_repr = match BuiltInType::with_type(&ok_ty) {
+                        Some(built_in) => {
+                            built_in.to_extern_rust_ident(ok_ty.span(), swift_bridge_path)
+                        }
+                        None => quote_spanned! {ok_ty.span()=> *mut std::ffi::c_void },
+                    };
+
+                    quote! {#arrow #swift_bridge_path::result::ResultAbi<#ok_repr> }
+                } else if let Some((elem_ty, _built_in, len)) = Self::array_info(&ty) {
+                    // C can't return `[T; N]` by value, so we box it up and
+                    // hand back the pointer; the length is statically known
+                    // to both sides from the original signature, so no
+                    // separate length out-param is needed.
+                    let len = proc_macro2::Literal::usize_unsuffixed(len);
+                    quote_spanned! {ty.span()=> #arrow *mut [#elem_ty; #len] }
+                } else if let Some(built_in) = BuiltInType::with_type(&ty) {
                     let ty = built_in.to_extern_rust_ident(ty.span(), swift_bridge_path);
                     quote! {#arrow #ty}
                 } else {
@@ -86,6 +137,75 @@ impl ParsedExternFn {
         ret
     }
 
+    /// If this function's return type is `Result<T, E>`, returns the `T` and
+    /// `E` types so that fallible-function codegen can build the throwing
+    /// ABI. The error side always crosses the boundary boxed, the same as
+    /// any other opaque type.
+    pub(crate) fn fallible_return_types(&self) -> Option<(Type, Type)> {
+        let ty = match &self.func.sig.output {
+            ReturnType::Type(_, ty) => ty.deref(),
+            ReturnType::Default => return None,
+        };
+
+        let path = match ty {
+            Type::Path(path) => path,
+            _ => return None,
+        };
+
+        let segment = path.path.segments.last()?;
+        if segment.ident != "Result" {
+            return None;
+        }
+
+        let args = match &segment.arguments {
+            PathArguments::AngleBracketed(args) => args,
+            _ => return None,
+        };
+
+        let mut generics = args.args.iter().filter_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty.clone()),
+            _ => None,
+        });
+
+        let ok_ty = generics.next()?;
+        let err_ty = generics.next()?;
+
+        Some((ok_ty, err_ty))
+    }
+
+    /// Parses a `#[swift_bridge(namespace = "...")]` out of a set of item
+    /// attributes, returning the namespace string if present.
+    ///
+    /// This works directly off of `syn::Attribute`s so that it can be reused
+    /// for both module-level and extern-block-level `#[swift_bridge(...)]`
+    /// attributes. Called from `ParsedExternFn::new`, which is where a
+    /// module-level parser should build every `ParsedExternFn` from so that
+    /// `namespace` is never left unset by accident.
+    pub(crate) fn parse_namespace_attr(attrs: &[Attribute]) -> Option<String> {
+        for attr in attrs {
+            if !attr.path.is_ident("swift_bridge") {
+                continue;
+            }
+
+            let meta = match attr.parse_meta() {
+                Ok(Meta::List(list)) => list,
+                _ => continue,
+            };
+
+            for nested in meta.nested {
+                if let NestedMeta::Meta(Meta::NameValue(nv)) = nested {
+                    if nv.path.is_ident("namespace") {
+                        if let syn::Lit::Str(namespace) = nv.lit {
+                            return Some(namespace.value());
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
     pub fn extern_swift_linked_fn_new(&self) -> Ident {
         let sig = &self.func.sig;
 
@@ -102,6 +222,82 @@ impl ParsedExternFn {
     }
 }
 
+impl ParsedExternFn {
+    /// The `#[cfg(...)]` attributes (if any) attached to this function. These
+    /// need to be copied verbatim onto the generated `extern "C"` shim, and
+    /// their predicate re-evaluated by the C header and Swift generators --
+    /// which run in a separate build-time process and so can't rely on the
+    /// compiler's own `cfg!` -- so that the Rust shim, the header entry, and
+    /// the Swift wrapper all agree on whether this function exists.
+    pub fn cfg_attrs(&self) -> Vec<&Attribute> {
+        self.func
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("cfg"))
+            .collect()
+    }
+
+    /// Whether this function's `#[cfg(...)]` attributes hold given the set of
+    /// currently enabled cargo features. A from-scratch `any`/`all`/`not`/
+    /// `feature` evaluator -- mirrors cxx's `CfgExpr`, implemented locally
+    /// here since the C header and Swift generators run outside of the
+    /// bridged crate and have no other way to evaluate a cfg predicate.
+    ///
+    /// A predicate (or sub-predicate) that we don't understand -- e.g.
+    /// `cfg(target_os = "...")`, which depends on the Swift build's target
+    /// rather than a cargo feature -- is treated as *not* holding. Defaulting
+    /// to "included" would be the more dangerous failure mode: it would
+    /// silently emit a header/Swift entry for a function the Rust compiler
+    /// ends up not compiling in at all.
+    pub fn cfg_predicate_holds(&self, enabled_features: &[&str]) -> bool {
+        self.cfg_attrs().iter().all(|attr| {
+            let list = match attr.parse_meta() {
+                Ok(Meta::List(list)) => list,
+                _ => return false,
+            };
+
+            list.nested
+                .iter()
+                .all(|nested| Self::eval_cfg_predicate(nested, enabled_features) == Some(true))
+        })
+    }
+
+    /// `None` if `nested` or anything inside it is not understood, so that an
+    /// unknown predicate can't be turned into "holds" by an enclosing `not`.
+    fn eval_cfg_predicate(nested: &NestedMeta, enabled_features: &[&str]) -> Option<bool> {
+        let meta = match nested {
+            NestedMeta::Meta(meta) => meta,
+            NestedMeta::Lit(_) => return None,
+        };
+
+        let eval_each = |list: &syn::MetaList| {
+            list.nested
+                .iter()
+                .map(|n| Self::eval_cfg_predicate(n, enabled_features))
+                .collect::<Option<Vec<bool>>>()
+        };
+
+        match meta {
+            Meta::NameValue(nv) if nv.path.is_ident("feature") => match &nv.lit {
+                syn::Lit::Str(feature) => {
+                    Some(enabled_features.contains(&feature.value().as_str()))
+                }
+                _ => None,
+            },
+            Meta::List(list) if list.path.is_ident("any") => {
+                eval_each(list).map(|vals| vals.contains(&true))
+            }
+            Meta::List(list) if list.path.is_ident("all") => {
+                eval_each(list).map(|vals| !vals.contains(&false))
+            }
+            Meta::List(list) if list.path.is_ident("not") => {
+                eval_each(list).map(|vals| !vals.contains(&true))
+            }
+            _ => None,
+        }
+    }
+}
+
 impl ParsedExternFn {
     // extern Rust:
     // fn foo (&self, arg1: u8, arg2: u32, &SomeType)
@@ -138,7 +334,15 @@ impl ParsedExternFn {
 
                     let mut arg = quote! {#pat};
 
-                    if BuiltInType::with_type(&pat_ty.ty).is_none() {
+                    if Self::array_info(&pat_ty.ty).is_some() {
+                        // `to_extern_c_params` declares this parameter as
+                        // `*const [T; N]` (C can't pass an array by value
+                        // any more than it can return one), so we just
+                        // dereference the pointer we were actually handed --
+                        // casting it, as if it had arrived as the array
+                        // value itself, doesn't type-check.
+                        arg = quote! { unsafe { *#arg } };
+                    } else if BuiltInType::with_type(&pat_ty.ty).is_none() {
                         let (maybe_ref, maybe_mut) = match pat_ty.ty.deref() {
                             Type::Reference(ty_ref) => (Some(ty_ref.and_token), ty_ref.mutability),
                             _ => (None, None),
@@ -175,14 +379,27 @@ impl ParsedExternFn {
                             params.push("void* self".to_string());
                         }
                         _ => {
-                            let ty = if let Some(built_in) = BuiltInType::with_type(&pat_ty.ty) {
-                                built_in.to_c().to_string()
+                            let arg_name = pat_ty.pat.to_token_stream().to_string();
+
+                            if let Some((_elem_ty, built_in, len)) =
+                                Self::array_info(&pat_ty.ty)
+                            {
+                                params.push(format!(
+                                    "{} {}[{}]",
+                                    built_in.to_c(),
+                                    arg_name,
+                                    len
+                                ));
                             } else {
-                                pat.to_token_stream().to_string()
-                            };
+                                let ty = if let Some(built_in) = BuiltInType::with_type(&pat_ty.ty)
+                                {
+                                    built_in.to_c().to_string()
+                                } else {
+                                    "void*".to_string()
+                                };
 
-                            let arg_name = pat_ty.pat.to_token_stream().to_string();
-                            params.push(format!("{} {}", ty, arg_name));
+                                params.push(format!("{} {}", ty, arg_name));
+                            }
                         }
                     };
                 }
@@ -200,7 +417,17 @@ impl ParsedExternFn {
         match &self.func.sig.output {
             ReturnType::Default => "void".to_string(),
             ReturnType::Type(_, ty) => {
-                if let Some(ty) = BuiltInType::with_type(&ty) {
+                if self.fallible_return_types().is_some() {
+                    // Already `typedef`'d by `fallible_return_c_struct_def`,
+                    // spliced into the header just above this entry by
+                    // `to_c_header_entry`, so the bare name is a complete type.
+                    self.fallible_return_c_struct_name()
+                } else if let Some((_elem_ty, built_in, _len)) = Self::array_info(&ty) {
+                    // C can't return an array by value; the element count is
+                    // fixed and known to both sides, so a bare pointer is
+                    // enough for the Swift wrapper to reconstruct it.
+                    format!("{}*", built_in.to_c())
+                } else if let Some(ty) = BuiltInType::with_type(&ty) {
                     ty.to_c()
                 } else {
                     "void*".to_string()
@@ -209,20 +436,131 @@ impl ParsedExternFn {
         }
     }
 
+    /// A unique, per-function name for the tagged struct that the C header
+    /// and the `extern "C"` shim agree on for `Result<T, E>` returns, since C
+    /// has no generics and so each fallible function needs its own struct.
+    fn fallible_return_c_struct_name(&self) -> String {
+        format!("{}_Result", self.prefixed_fn_name())
+    }
+
+    /// The `typedef struct { bool is_ok; union { ... } payload; } Name;`
+    /// definition backing this function's fallible-return ABI, or `None` if
+    /// it isn't fallible. Mirrors the shape `to_extern_c_fn` actually
+    /// constructs: the `Ok` arm stores the built-in payload in place, the
+    /// `Err` arm is always an opaque boxed pointer, since C has no generics
+    /// and so can't express "whatever `E` this particular function boxes up"
+    /// any more precisely than that.
+    fn fallible_return_c_struct_def(&self) -> Option<String> {
+        let (ok_ty, _err_ty) = self.fallible_return_types()?;
+
+        let ok_c_ty = BuiltInType::with_type(&ok_ty)
+            .map(|built_in| built_in.to_c().to_string())
+            .unwrap_or_else(|| "void*".to_string());
+        let struct_name = self.fallible_return_c_struct_name();
+
+        Some(format!(
+            "typedef struct {{\n    \
+             bool is_ok;\n    \
+             union {{\n        \
+             {ok_c_ty} ok;\n        \
+             void* err;\n    \
+             }} payload;\n}} {struct_name};",
+            ok_c_ty = ok_c_ty,
+            struct_name = struct_name,
+        ))
+    }
+
+    /// The full C header declaration for this function -- the
+    /// `fallible_return_c_struct_def` typedef first if it returns
+    /// `Result<T, E>` (so the struct it references is always defined before
+    /// use), then the return type, name, and params -- or `None` if its
+    /// `#[cfg(...)]` doesn't hold for `enabled_features`. This is the gate
+    /// that `to_c_header_params` and `to_c_header_return` alone can't
+    /// provide: whether the whole entry should be emitted at all, which has
+    /// to agree with whether `rustc` actually compiled the function in.
+    pub fn to_c_header_entry(&self, enabled_features: &[&str]) -> Option<String> {
+        if !self.cfg_predicate_holds(enabled_features) {
+            return None;
+        }
+
+        let struct_def = self
+            .fallible_return_c_struct_def()
+            .map(|def| format!("{}\n", def))
+            .unwrap_or_default();
+
+        Some(format!(
+            "{struct_def}{ret} {name}({params});",
+            struct_def = struct_def,
+            ret = self.to_c_header_return(),
+            name = self.prefixed_fn_name(),
+            params = self.to_c_header_params()
+        ))
+    }
+
+    /// The name of the `extern "C"` function that frees a boxed `[T; N]`
+    /// returned by this function (see `to_extern_c_array_free_fn`).
+    fn array_return_free_fn_name(&self) -> Ident {
+        Ident::new(
+            &format!("{}_free", self.prefixed_fn_name()),
+            self.func.sig.ident.span(),
+        )
+    }
+
+    /// If `ty` is a fixed-size array of a built-in scalar (`[T; N]`), returns
+    /// the element type (both as the original `syn::Type` and the matching
+    /// `BuiltInType`) and `N`. `N` must be a literal so that the length is
+    /// known at compile time on both the Rust and the generated C/Swift
+    /// sides -- arrays with a `const`-expression length aren't supported.
+    fn array_info(ty: &Type) -> Option<(&Type, BuiltInType, usize)> {
+        let array = match ty {
+            Type::Array(array) => array,
+            _ => return None,
+        };
+
+        let len = match &array.len {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(len),
+                ..
+            }) => len.base10_parse::<usize>().ok()?,
+            _ => return None,
+        };
+
+        let elem_ty = BuiltInType::with_type(&array.elem)?;
+
+        Some((&array.elem, elem_ty, len))
+    }
+
     pub fn contains_ints(&self) -> bool {
         if let ReturnType::Type(_, ty) = &self.func.sig.output {
-            if let Some(ty) = BuiltInType::with_type(&ty) {
-                if ty.needs_include_int_header() {
-                    return true;
+            let scalar_ty = match self.fallible_return_types() {
+                Some((ok_ty, _err_ty)) => Some(ok_ty),
+                None => match Self::array_info(ty) {
+                    Some((elem_ty, _built_in, _len)) => Some(elem_ty.clone()),
+                    None => Some((**ty).clone()),
+                },
+            };
+
+            if let Some(scalar_ty) = scalar_ty {
+                if let Some(ty) = BuiltInType::with_type(&scalar_ty) {
+                    if ty.needs_include_int_header() {
+                        return true;
+                    }
                 }
             }
         }
 
         for param in &self.func.sig.inputs {
             if let FnArg::Typed(pat_ty) = param {
-                if let Some(ty) = BuiltInType::with_type(&pat_ty.ty) {
-                    if ty.needs_include_int_header() {
-                        return true;
+                let scalar_ty = match Self::array_info(&pat_ty.ty) {
+                    Some((elem_ty, _built_in, _len)) => Some(elem_ty.clone()),
+                    None => Some((*pat_ty.ty).clone()),
+                };
+
+                if let Some(scalar_ty) = scalar_ty {
+                    if let Some(ty) = BuiltInType::with_type(&scalar_ty) {
+                        if ty.needs_include_int_header() {
+                            return true;
+                        }
                     }
                 }
             }
@@ -234,6 +572,12 @@ impl ParsedExternFn {
 
 impl ParsedExternFn {
     pub fn link_name(&self) -> String {
+        let namespace = self
+            .namespace
+            .as_ref()
+            .map(|ns| format!("${}", ns))
+            .unwrap_or_default();
+
         let host_type = self
             .associated_type
             .as_ref()
@@ -241,14 +585,20 @@ impl ParsedExternFn {
             .unwrap_or("".to_string());
 
         format!(
-            "{}{}${}",
+            "{}{}{}${}",
             SWIFT_BRIDGE_PREFIX,
+            namespace,
             host_type,
             self.func.sig.ident.to_string()
         )
     }
 
     pub fn prefixed_fn_name(&self) -> Ident {
+        let namespace_prefix = self
+            .namespace
+            .as_ref()
+            .map(|ns| format!("{}_", ns))
+            .unwrap_or_default();
         let host_type_prefix = self
             .associated_type
             .as_ref()
@@ -257,8 +607,9 @@ impl ParsedExternFn {
         let fn_name = &self.func.sig.ident;
         let prefixed_fn_name = Ident::new(
             &format!(
-                "{}{}{}",
+                "{}{}{}{}",
                 SWIFT_BRIDGE_PREFIX,
+                namespace_prefix,
                 host_type_prefix,
                 fn_name.to_string()
             ),
@@ -373,6 +724,330 @@ mod tests {
         assert_eq!(module.functions.len(), 2);
     }
 
+    /// Verify that a `-> Result<T, E>` return type is recognized as fallible
+    /// and generates the tagged-struct ABI rather than being treated as an
+    /// opaque `void*`.
+    #[test]
+    fn recognizes_fallible_return_type() {
+        let tokens = quote! {
+            #[swift_bridge::bridge]
+            mod ffi {
+                extern "Rust" {
+                    fn try_thing() -> Result<u8, String>;
+                }
+            }
+        };
+        let module = parse_ok(tokens);
+        let method = &module.functions[0];
+
+        let (ok_ty, err_ty) = method.fallible_return_types().unwrap();
+        assert_eq!(ok_ty.to_token_stream().to_string(), "u8");
+        assert_eq!(err_ty.to_token_stream().to_string(), "String");
+
+        assert!(method.to_c_header_return().ends_with("_Result"));
+    }
+
+    /// Verify that a fallible function's header entry actually defines the
+    /// `struct { bool is_ok; union {...} payload; }` that `to_c_header_return`
+    /// references, rather than emitting a declaration pointing at an
+    /// undefined type.
+    #[test]
+    fn fallible_return_header_entry_defines_its_struct() {
+        let tokens = quote! {
+            #[swift_bridge::bridge]
+            mod ffi {
+                extern "Rust" {
+                    fn try_thing() -> Result<u8, String>;
+                }
+            }
+        };
+        let module = parse_ok(tokens);
+        let method = &module.functions[0];
+
+        let entry = method.to_c_header_entry(&[]).unwrap();
+        let struct_name = method.to_c_header_return();
+
+        assert!(entry.contains("typedef struct {"), "entry was: {}", entry);
+        assert!(entry.contains("bool is_ok;"), "entry was: {}", entry);
+        assert!(entry.contains("uint8_t ok;"), "entry was: {}", entry);
+        assert!(entry.contains("void* err;"), "entry was: {}", entry);
+        assert!(
+            entry.contains(&format!("}} {};", struct_name)),
+            "entry was: {}",
+            entry
+        );
+
+        // The typedef must appear before the function declaration that uses it.
+        let typedef_pos = entry.find("typedef").unwrap();
+        let decl = format!("{} {}(", struct_name, method.prefixed_fn_name());
+        let decl_pos = entry.find(&decl).unwrap();
+        assert!(typedef_pos < decl_pos);
+    }
+
+    /// Verify that a fallible function's generated `extern "C"` shim branches
+    /// on the `Ok`/`Err` tag and writes into the `ResultAbi` union, and that
+    /// its Swift wrapper is a real `throws` function rather than an opaque
+    /// pointer-returning one.
+    #[test]
+    fn fallible_return_generates_throwing_abi() {
+        let tokens = quote! {
+            #[swift_bridge::bridge]
+            mod ffi {
+                extern "Rust" {
+                    fn try_thing() -> Result<u8, String>;
+                }
+            }
+        };
+        let module = parse_ok(tokens);
+        let method = &module.functions[0];
+
+        let swift_bridge_path: Path = syn::parse_quote! { swift_bridge };
+
+        let shim = method.to_extern_c_fn(&swift_bridge_path).to_string();
+        assert!(shim.contains("is_ok"));
+        assert!(shim.contains("ResultAbi"));
+        assert!(shim.contains("ResultPayload"));
+
+        let swift_func = method.to_swift_func();
+        assert!(swift_func.contains("throws"));
+        assert!(swift_func.contains("is_ok"));
+    }
+
+    /// Verify that `#[swift_bridge(namespace = "...")]` is actually parsed
+    /// off of real attribute tokens, rather than only existing as a field
+    /// that has to be populated by hand.
+    ///
+    /// Note: this exercises the attribute parser in isolation. Calling it for
+    /// every function in a module/extern block so that `ParsedExternFn`s
+    /// built during real macro expansion end up with `namespace` populated
+    /// is the job of the module-level parser, which lives outside of this
+    /// file.
+    #[test]
+    fn parses_namespace_attribute_from_real_tokens() {
+        let with_namespace: Attribute = syn::parse_quote! {
+            #[swift_bridge(namespace = "myns")]
+        };
+        assert_eq!(
+            ParsedExternFn::parse_namespace_attr(&[with_namespace]),
+            Some("myns".to_string())
+        );
+
+        let without_namespace: Attribute = syn::parse_quote! {
+            #[swift_bridge(init)]
+        };
+        assert_eq!(
+            ParsedExternFn::parse_namespace_attr(&[without_namespace]),
+            None
+        );
+    }
+
+    /// Verify that a module-level namespace is woven into both the mangled
+    /// `link_name` and the `prefixed_fn_name` used by the generated shim, so
+    /// that two bridge modules declaring the same type don't collide.
+    ///
+    /// Drives this through `ParsedExternFn::new` with the real
+    /// `#[swift_bridge(namespace = "...")]` attribute tokens a surrounding
+    /// extern block would carry, rather than setting the field by hand, so
+    /// this exercises the same path a module-level parser would.
+    #[test]
+    fn namespace_is_woven_into_mangled_names() {
+        let func: ForeignItemFn = syn::parse_quote! { fn new (); };
+        let extern_block_attrs: Vec<Attribute> = vec![syn::parse_quote! {
+            #[swift_bridge(namespace = "myns")]
+        }];
+        let parsed = ParsedExternFn::new(
+            func,
+            None,
+            false,
+            HostLang::Rust,
+            &extern_block_attrs,
+        );
+
+        assert_eq!(
+            parsed.link_name(),
+            format!("{}$myns$new", SWIFT_BRIDGE_PREFIX)
+        );
+        assert_eq!(
+            parsed.prefixed_fn_name().to_string(),
+            format!("{}myns_new", SWIFT_BRIDGE_PREFIX)
+        );
+    }
+
+    /// Verify that a fixed-size array param is passed as `T arg[N]` in the C
+    /// header, crosses the `extern "C"` boundary as `*const [T; N]` (not by
+    /// value -- C can't pass an array by value), and is reconstructed from
+    /// that pointer (not heap-allocated) on the Rust side.
+    #[test]
+    fn fixed_size_array_param() {
+        let tokens = quote! {
+            #[swift_bridge::bridge]
+            mod ffi {
+                extern "Rust" {
+                    fn takes_array(arg: [u8; 16]);
+                }
+            }
+        };
+        let module = parse_ok(tokens);
+        let method = &module.functions[0];
+        let swift_bridge_path: Path = syn::parse_quote! { swift_bridge };
+
+        assert_eq!(method.to_c_header_params(), "uint8_t arg[16]");
+
+        let shim = method.to_extern_c_fn(&swift_bridge_path).to_string();
+        assert!(shim.contains("arg : * const"), "shim was: {}", shim);
+        assert!(shim.contains("16"), "shim was: {}", shim);
+
+        assert_tokens_eq(&method.to_rust_call_args(), &quote! { unsafe { *arg } });
+    }
+
+    /// Verify that a fixed-size array return is actually boxed up on the way
+    /// out (rather than falling back to an untyped `void*`), that a matching
+    /// free function is generated for it, and that the Swift wrapper copies
+    /// the elements out into a native array and frees the allocation.
+    #[test]
+    fn fixed_size_array_return_is_allocated_and_freed() {
+        let tokens = quote! {
+            #[swift_bridge::bridge]
+            mod ffi {
+                extern "Rust" {
+                    fn returns_array() -> [u8; 4];
+                }
+            }
+        };
+        let module = parse_ok(tokens);
+        let method = &module.functions[0];
+        let swift_bridge_path: Path = syn::parse_quote! { swift_bridge };
+
+        assert_tokens_eq(
+            &method.rust_return_type(&swift_bridge_path),
+            &quote! { -> *mut [u8; 4] },
+        );
+        assert_eq!(method.to_c_header_return(), "uint8_t*");
+
+        let shim = method.to_extern_c_fn(&swift_bridge_path).to_string();
+        assert!(shim.contains("Box :: into_raw"));
+
+        let free_fn = method.to_extern_c_array_free_fn().unwrap().to_string();
+        assert!(free_fn.contains("Box :: from_raw"));
+
+        let swift_func = method.to_swift_func();
+        assert!(swift_func.contains("_free"));
+        assert!(swift_func.contains("[UInt8]") || swift_func.contains("UInt8"));
+    }
+
+    /// Verify that a `#[cfg(feature = "...")]` on a bridged function is kept
+    /// on `ParsedExternFn` and evaluated consistently against a given set of
+    /// enabled features, rather than being silently dropped.
+    #[test]
+    fn cfg_gated_fn_is_evaluated_consistently() {
+        let tokens = quote! {
+            #[swift_bridge::bridge]
+            mod ffi {
+                extern "Rust" {
+                    #[cfg(feature = "some-feature")]
+                    fn gated();
+
+                    fn ungated();
+                }
+            }
+        };
+        let module = parse_ok(tokens);
+        let gated = &module.functions[0];
+        let ungated = &module.functions[1];
+
+        assert_eq!(gated.cfg_attrs().len(), 1);
+        assert!(gated.cfg_predicate_holds(&["some-feature"]));
+        assert!(!gated.cfg_predicate_holds(&["other-feature"]));
+
+        assert_eq!(ungated.cfg_attrs().len(), 0);
+        assert!(ungated.cfg_predicate_holds(&[]));
+    }
+
+    /// Verify that a gated function's cfg predicate is actually consulted by
+    /// the C header and Swift entry points, rather than just being
+    /// computable-but-unused: a gated-out function produces no header entry
+    /// and no Swift wrapper, while a gated-in one produces both.
+    #[test]
+    fn cfg_predicate_gates_header_and_swift_emission() {
+        let tokens = quote! {
+            #[swift_bridge::bridge]
+            mod ffi {
+                extern "Rust" {
+                    #[cfg(feature = "some-feature")]
+                    fn gated();
+                }
+            }
+        };
+        let module = parse_ok(tokens);
+        let gated = &module.functions[0];
+
+        assert!(gated.to_c_header_entry(&["some-feature"]).is_some());
+        assert!(gated.to_c_header_entry(&["other-feature"]).is_none());
+
+        assert!(gated.to_swift_func_gated(&["some-feature"]).is_some());
+        assert!(gated.to_swift_func_gated(&["other-feature"]).is_none());
+    }
+
+    /// Verify that a cfg predicate we don't understand (anything other than
+    /// a bare `feature = "..."`, possibly nested in `any`/`all`/`not`) is
+    /// treated as not holding, rather than defaulting to "included".
+    #[test]
+    fn unrecognized_cfg_predicate_does_not_default_to_included() {
+        let tokens = quote! {
+            #[swift_bridge::bridge]
+            mod ffi {
+                extern "Rust" {
+                    #[cfg(target_os = "ios")]
+                    fn gated();
+                }
+            }
+        };
+        let module = parse_ok(tokens);
+        let gated = &module.functions[0];
+
+        assert!(!gated.cfg_predicate_holds(&[]));
+        assert!(!gated.cfg_predicate_holds(&["some-feature"]));
+    }
+
+    /// Verify that a function's parameters are actually forwarded: the
+    /// `extern "C"` shim takes real C-ABI types (a pointer for a foreign
+    /// type, not the bare Rust type), and the Swift wrapper both declares
+    /// and passes every parameter through to the shim call, rather than
+    /// dropping them as the 0-arg tests elsewhere in this file wouldn't
+    /// catch.
+    #[test]
+    fn params_are_forwarded_to_shim_and_swift_wrapper() {
+        let tokens = quote! {
+            #[swift_bridge::bridge]
+            mod ffi {
+                extern "Rust" {
+                    type Foo;
+
+                    fn add(a: u8, b: &Foo);
+                }
+            }
+        };
+        let module = parse_ok(tokens);
+        let method = &module.functions[0];
+        let swift_bridge_path: Path = syn::parse_quote! { swift_bridge };
+
+        let shim = method.to_extern_c_fn(&swift_bridge_path).to_string();
+        assert!(shim.contains("a : u8"), "shim was: {}", shim);
+        assert!(shim.contains("b : * const Foo"), "shim was: {}", shim);
+
+        let swift_func = method.to_swift_func();
+        assert!(
+            swift_func.contains("a: UInt8"),
+            "swift func was: {}",
+            swift_func
+        );
+        assert!(
+            swift_func.contains(&format!("{}(a, b)", method.prefixed_fn_name())),
+            "swift func was: {}",
+            swift_func
+        );
+    }
+
     fn parse_ok(tokens: TokenStream) -> SwiftBridgeModule {
         let module_and_errors: SwiftBridgeModuleAndErrors = syn::parse2(tokens).unwrap();
         module_and_errors.module
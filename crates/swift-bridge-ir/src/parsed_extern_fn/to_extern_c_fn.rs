@@ -0,0 +1,157 @@
+use super::ParsedExternFn;
+use crate::built_in_types::BuiltInType;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::ops::Deref;
+use syn::spanned::Spanned;
+use syn::{FnArg, Pat, Path, ReturnType, Type};
+
+impl ParsedExternFn {
+    /// Builds the `#[export_name = ...] pub extern "C" fn ...` shim that Swift
+    /// (and, per the generated header, C) call into.
+    ///
+    /// For a fallible `-> Result<T, E>` function the real call is wrapped in
+    /// a tagged `ResultAbi<T>`: the `Ok` arm stores the built-in value
+    /// in-place in the `ResultPayload` union, the `Err` arm boxes `E` the
+    /// same way any other owned foreign type crosses the boundary. Exactly
+    /// one `payload` union arm is written, selected by `is_ok`.
+    ///
+    /// This also carries the function's `#[cfg(...)]` attributes (if any)
+    /// over onto the shim verbatim, so that `rustc` only compiles this
+    /// `extern "C"` fn in under the same conditions as the original
+    /// declaration -- the C header and Swift generators separately consult
+    /// `cfg_predicate_holds` to decide whether to emit a matching entry.
+    pub(crate) fn to_extern_c_fn(&self, swift_bridge_path: &Path) -> TokenStream {
+        let cfg_attrs = self.cfg_attrs();
+        let link_name = self.link_name();
+        let shim_name = self.prefixed_fn_name();
+        let inputs = self.to_extern_c_params(swift_bridge_path);
+        let call_args = self.to_rust_call_args();
+        let sig_name = &self.func.sig.ident;
+        let ret = self.rust_return_type(swift_bridge_path);
+
+        let call = quote! { super::#sig_name(#call_args) };
+
+        let body = if let Some((ok_ty, _err_ty)) = self.fallible_return_types() {
+            // A non-built-in `Ok` value is boxed, matching the `*mut c_void`
+            // that `rust_return_type` declares for it.
+            let ok = if BuiltInType::with_type(&ok_ty).is_some() {
+                quote! { ok }
+            } else {
+                quote! { Box::into_raw(Box::new(ok)) as *mut std::ffi::c_void }
+            };
+
+            quote! {
+                match #call {
+                    Ok(ok) => #swift_bridge_path::result::ResultAbi {
+                        is_ok: true,
+                        payload: #swift_bridge_path::result::ResultPayload {
+                            ok: std::mem::ManuallyDrop::new(#ok),
+                        },
+                    },
+                    Err(err) => #swift_bridge_path::result::ResultAbi {
+                        is_ok: false,
+                        payload: #swift_bridge_path::result::ResultPayload {
+                            err: Box::into_raw(Box::new(err)) as *mut std::ffi::c_void,
+                        },
+                    },
+                }
+            }
+        } else if matches!(&self.func.sig.output, ReturnType::Type(_, ty) if Self::array_info(ty).is_some())
+        {
+            // The fixed-size array is returned by value from the real
+            // function, but C can't return arrays by value, so we box it up
+            // here. `to_extern_c_array_free_fn` generates the matching
+            // free-function that releases this allocation once the caller
+            // (Swift, or C directly) has copied the elements out.
+            quote! { Box::into_raw(Box::new(#call)) }
+        } else {
+            quote! { #call }
+        };
+
+        quote! {
+            #(#cfg_attrs)*
+            #[export_name = #link_name]
+            pub extern "C" fn #shim_name(#inputs) #ret {
+                #body
+            }
+        }
+    }
+
+    /// The `extern "C"` fn's parameter list, in real C-ABI types rather than
+    /// the original Rust signature's: built-in scalars keep their extern
+    /// representation, the receiver crosses as an untyped `*mut c_void`, any
+    /// other foreign-type param crosses as a raw pointer to the concrete type
+    /// (matching what `to_rust_call_args` dereferences on the other side),
+    /// and a fixed-size
+    /// array crosses as `*const [T; N]` -- C can't take `[T; N]` by value
+    /// either, and a pointer is what `to_c_header_params`'s decayed
+    /// `T arg[N]` already promises callers.
+    fn to_extern_c_params(&self, swift_bridge_path: &Path) -> TokenStream {
+        let mut params = vec![];
+
+        for arg in &self.func.sig.inputs {
+            match arg {
+                FnArg::Receiver(_) => {
+                    params.push(quote! { _self: *mut std::ffi::c_void });
+                }
+                FnArg::Typed(pat_ty) => {
+                    let pat = &pat_ty.pat;
+
+                    if let Pat::Ident(pat_ident) = pat.deref() {
+                        if pat_ident.ident == "self" {
+                            params.push(quote! { _self: *mut std::ffi::c_void });
+                            continue;
+                        }
+                    }
+
+                    let ty = if let Some((elem_ty, _built_in, len)) = Self::array_info(&pat_ty.ty)
+                    {
+                        quote! { *const [#elem_ty; #len] }
+                    } else if let Some(built_in) = BuiltInType::with_type(&pat_ty.ty) {
+                        built_in.to_extern_rust_ident(pat_ty.ty.span(), swift_bridge_path)
+                    } else {
+                        match pat_ty.ty.deref() {
+                            Type::Reference(ty_ref) => {
+                                let elem = &ty_ref.elem;
+                                match ty_ref.mutability {
+                                    Some(mutability) => quote! { *#mutability #elem },
+                                    None => quote! { *const #elem },
+                                }
+                            }
+                            owned => quote! { *mut #owned },
+                        }
+                    };
+
+                    params.push(quote! { #pat: #ty });
+                }
+            }
+        }
+
+        quote! { #(#params),* }
+    }
+
+    /// The free function paired with a fixed-size-array-returning shim's
+    /// boxed allocation (see `to_extern_c_fn`), or `None` if this function
+    /// doesn't return `[T; N]`. Swift calls this immediately after copying
+    /// the elements out into a native array/tuple.
+    pub(crate) fn to_extern_c_array_free_fn(&self) -> Option<TokenStream> {
+        let ty = match &self.func.sig.output {
+            ReturnType::Type(_, ty) => ty,
+            ReturnType::Default => return None,
+        };
+        let (elem_ty, _built_in, len) = Self::array_info(ty)?;
+
+        let free_fn_name = self.array_return_free_fn_name();
+        let export_name = free_fn_name.to_string();
+
+        Some(quote! {
+            #[export_name = #export_name]
+            pub extern "C" fn #free_fn_name(ptr: *mut [#elem_ty; #len]) {
+                unsafe {
+                    drop(Box::from_raw(ptr));
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,35 @@
+use super::ParsedExternFn;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Path;
+
+impl ParsedExternFn {
+    /// Builds the body of the safe Rust function that calls into an
+    /// `extern "Swift"` function.
+    ///
+    /// For a fallible `-> Result<T, E>` function this unwraps the tagged
+    /// `ResultAbi<T>` union that the Swift side hands back: the `Ok` arm is
+    /// read out of the union in place, the `Err` arm takes ownership of the
+    /// boxed `E` that Swift boxed up on the throwing path.
+    pub(crate) fn to_rust_impl_call_swift(&self, swift_bridge_path: &Path) -> TokenStream {
+        let extern_swift_fn = self.extern_swift_linked_fn_new();
+        let call_args = self.to_rust_call_args();
+
+        let call = quote! { #extern_swift_fn(#call_args) };
+
+        if let Some((_ok_ty, err_ty)) = self.fallible_return_types() {
+            quote! {
+                {
+                    let result: #swift_bridge_path::result::ResultAbi<_> = #call;
+                    if result.is_ok {
+                        Ok(unsafe { std::mem::ManuallyDrop::into_inner(result.payload.ok) })
+                    } else {
+                        Err(*unsafe { Box::from_raw(result.payload.err as *mut #err_ty) })
+                    }
+                }
+            }
+        } else {
+            call
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use super::ParsedExternFn;
+use crate::built_in_types::BuiltInType;
+use quote::ToTokens;
+use std::ops::Deref;
+use syn::{FnArg, Pat, ReturnType};
+
+/// A Swift-side call to the shim, split into the pieces every branch of
+/// `to_swift_func` needs: the wrapper's own `(arg: Type, ...)` parameter
+/// list, and the `(arg, ...)` argument list to pass straight through to the
+/// shim call.
+///
+/// An array-typed param can't be hauled into a C varargs-free call directly
+/// -- Swift has to hand over the contiguous buffer's base address instead --
+/// so those params additionally get a `prelude`/`postlude` pair that wraps
+/// the shim call in `withUnsafeBufferPointer { ... }`.
+struct SwiftCall {
+    params: String,
+    prelude: String,
+    call_args: String,
+    postlude: String,
+}
+
+impl ParsedExternFn {
+    fn swift_call(&self) -> SwiftCall {
+        let mut params = vec![];
+        let mut call_args = vec![];
+        let mut prelude = vec![];
+        let mut postlude = vec![];
+
+        for arg in &self.func.sig.inputs {
+            match arg {
+                FnArg::Receiver(_) => continue,
+                FnArg::Typed(pat_ty) => {
+                    if let Pat::Ident(pat_ident) = pat_ty.pat.deref() {
+                        if pat_ident.ident == "self" {
+                            continue;
+                        }
+                    }
+
+                    let arg_name = pat_ty.pat.to_token_stream().to_string();
+
+                    if let Some((_elem_ty, built_in, _len)) = Self::array_info(&pat_ty.ty) {
+                        let swift_elem_ty = built_in.to_swift_type();
+                        let ptr_name = format!("{}Ptr", arg_name);
+
+                        params.push(format!("{}: [{}]", arg_name, swift_elem_ty));
+                        prelude.push(format!(
+                            "{arg_name}.withUnsafeBufferPointer {{ {ptr_name} in",
+                            arg_name = arg_name,
+                            ptr_name = ptr_name
+                        ));
+                        postlude.push("}".to_string());
+                        call_args.push(format!("{}.baseAddress", ptr_name));
+                    } else if let Some(built_in) = BuiltInType::with_type(&pat_ty.ty) {
+                        params.push(format!("{}: {}", arg_name, built_in.to_swift_type()));
+                        call_args.push(arg_name);
+                    } else {
+                        params.push(format!("{}: UnsafeMutableRawPointer", arg_name));
+                        call_args.push(arg_name);
+                    }
+                }
+            }
+        }
+
+        SwiftCall {
+            params: params.join(", "),
+            prelude: prelude.join(" "),
+            call_args: call_args.join(", "),
+            postlude: postlude.join(""),
+        }
+    }
+
+    /// Wraps `body` (a `{shim_call}`-shaped expression or block) with the
+    /// `withUnsafeBufferPointer` closures needed to forward any array
+    /// arguments, indenting it the way the rest of this file's hand-written
+    /// Swift strings are indented.
+    fn wrap_array_prelude(call: &SwiftCall, body: &str) -> String {
+        if call.prelude.is_empty() {
+            return body.to_string();
+        }
+
+        format!(
+            "{prelude}\n    {body}\n{postlude}",
+            prelude = call.prelude,
+            body = body,
+            postlude = call.postlude
+        )
+    }
+}
+
+impl ParsedExternFn {
+    /// Builds the Swift wrapper function, or `None` if this function's
+    /// `#[cfg(...)]` doesn't hold for `enabled_features` -- the Swift
+    /// generator runs outside of the bridged crate and so has to make this
+    /// call itself instead of relying on `cfg!`, the same as the C header
+    /// generator does via `to_c_header_entry`.
+    pub(crate) fn to_swift_func_gated(&self, enabled_features: &[&str]) -> Option<String> {
+        if !self.cfg_predicate_holds(enabled_features) {
+            return None;
+        }
+
+        Some(self.to_swift_func())
+    }
+
+    /// Builds the Swift wrapper function.
+    ///
+    /// A fallible `-> Result<T, E>` function surfaces as `throws -> T`: it
+    /// reads the `is_ok` tag off of the union that the shim returns, either
+    /// returning the `Ok` payload or throwing a Swift error that owns the
+    /// boxed `E` (see `swift_error_class`).
+    pub(crate) fn to_swift_func(&self) -> String {
+        let swift_fn_name = self.func.sig.ident.to_string();
+        let shim_name = self.prefixed_fn_name().to_string();
+        let call = self.swift_call();
+        let params = &call.params;
+        let call_args = &call.call_args;
+
+        if let Some((ok_ty, err_ty)) = self.fallible_return_types() {
+            let ok_swift_ty = BuiltInType::with_type(&ok_ty)
+                .map(|built_in| built_in.to_swift_type())
+                .unwrap_or_else(|| "UnsafeMutableRawPointer".to_string());
+            let err_swift_ty = err_ty.to_token_stream().to_string();
+
+            let body = format!(
+                "let result = {shim_name}({call_args})\n\
+                 if result.is_ok {{\n    \
+                 return result.payload.ok\n\
+                 }} else {{\n    \
+                 throw {err_swift_ty}(ptr: result.payload.err)\n\
+                 }}",
+                shim_name = shim_name,
+                call_args = call_args,
+                err_swift_ty = err_swift_ty,
+            );
+            let body = Self::wrap_array_prelude(&call, &body);
+
+            format!(
+                "public func {swift_fn_name}({params}) throws -> {ok_swift_ty} {{\n    \
+                 {body}\n}}",
+                swift_fn_name = swift_fn_name,
+                params = params,
+                ok_swift_ty = ok_swift_ty,
+                body = body,
+            )
+        } else if let Some((_elem_ty, built_in, len)) = match &self.func.sig.output {
+            ReturnType::Type(_, ty) => Self::array_info(ty),
+            ReturnType::Default => None,
+        } {
+            // The shim hands back a pointer to a heap-allocated `[T; N]`; we
+            // copy the `len` elements out into a native Swift array and
+            // immediately free the Rust-side allocation via the paired
+            // `_free` shim rather than tying its lifetime to anything on the
+            // Swift side.
+            let swift_elem_ty = built_in.to_swift_type();
+            let free_fn_name = format!("{}_free", shim_name);
+            let elements = (0..len)
+                .map(|i| format!("ptr[{}]", i))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let body = format!(
+                "let ptr = {shim_name}({call_args})!\n\
+                 let array: [{swift_elem_ty}] = [{elements}]\n\
+                 {free_fn_name}(ptr)\n\
+                 return array",
+                shim_name = shim_name,
+                call_args = call_args,
+                swift_elem_ty = swift_elem_ty,
+                elements = elements,
+                free_fn_name = free_fn_name,
+            );
+            let body = Self::wrap_array_prelude(&call, &body);
+
+            format!(
+                "public func {swift_fn_name}({params}) -> [{swift_elem_ty}] {{\n    \
+                 {body}\n}}",
+                swift_fn_name = swift_fn_name,
+                params = params,
+                swift_elem_ty = swift_elem_ty,
+                body = body,
+            )
+        } else {
+            let body = Self::wrap_array_prelude(
+                &call,
+                &format!("{shim_name}({call_args})", shim_name = shim_name, call_args = call_args),
+            );
+
+            format!(
+                "public func {swift_fn_name}({params}) {{\n    {body}\n}}",
+                swift_fn_name = swift_fn_name,
+                params = params,
+                body = body,
+            )
+        }
+    }
+
+    /// The Swift `Error`-conforming wrapper class generated for a fallible
+    /// function's boxed error type. Its `deinit` frees the boxed Rust value
+    /// via the same `$_free` convention used for any other opaque type
+    /// crossing the boundary, so a thrown error doesn't leak the box it was
+    /// constructed from in `to_extern_c_fn`.
+    pub(crate) fn swift_error_class(err_swift_ty: &str) -> String {
+        format!(
+            "class {err_swift_ty}: Error {{\n    \
+             var ptr: UnsafeMutableRawPointer\n\n    \
+             init(ptr: UnsafeMutableRawPointer) {{\n        \
+             self.ptr = ptr\n    \
+             }}\n\n    \
+             deinit {{\n        \
+             __swift_bridge__${err_swift_ty}$_free(self.ptr)\n    \
+             }}\n}}",
+            err_swift_ty = err_swift_ty,
+        )
+    }
+}